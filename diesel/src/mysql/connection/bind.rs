@@ -0,0 +1,86 @@
+extern crate mysqlclient_sys as ffi;
+
+use std::os::raw as libc;
+use std::ptr;
+
+use crate::mysql::MysqlType;
+
+/// Owns the buffers that the underlying `MYSQL_BIND` structures point at for
+/// the lifetime of a single bound statement.
+pub(super) struct Binds {
+    data: Vec<BindData>,
+}
+
+impl Binds {
+    pub(super) fn from_input_data(
+        input: impl IntoIterator<Item = (MysqlType, Option<Vec<u8>>)>,
+    ) -> Self {
+        let data = input
+            .into_iter()
+            .map(|(tpe, value)| BindData::for_input(tpe, value))
+            .collect();
+        Binds { data }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// # Safety
+    ///
+    /// The returned `MYSQL_BIND` values point into `self`. The caller must
+    /// ensure `self` outlives any use of the returned buffer.
+    pub(super) unsafe fn mysql_binds(&mut self) -> Vec<ffi::MYSQL_BIND> {
+        self.data.iter_mut().map(BindData::mysql_bind).collect()
+    }
+}
+
+struct BindData {
+    tpe: MysqlType,
+    bytes: Option<Vec<u8>>,
+    length: libc::c_ulong,
+    is_null: ffi::my_bool,
+}
+
+impl BindData {
+    fn for_input(tpe: MysqlType, value: Option<Vec<u8>>) -> Self {
+        let length = value.as_ref().map_or(0, |v| v.len() as libc::c_ulong);
+        let is_null = value.is_none() as ffi::my_bool;
+        BindData {
+            tpe,
+            bytes: value,
+            length,
+            is_null,
+        }
+    }
+
+    unsafe fn mysql_bind(&mut self) -> ffi::MYSQL_BIND {
+        let mut bind: ffi::MYSQL_BIND = std::mem::zeroed();
+        bind.buffer_type = mysql_type_to_ffi(self.tpe);
+        bind.buffer = self
+            .bytes
+            .as_mut()
+            .map_or(ptr::null_mut(), |b| b.as_mut_ptr() as *mut libc::c_void);
+        bind.buffer_length = self.length;
+        bind.length = &mut self.length;
+        bind.is_null = &mut self.is_null;
+        bind
+    }
+}
+
+fn mysql_type_to_ffi(tpe: MysqlType) -> ffi::enum_field_types {
+    match tpe {
+        MysqlType::Tiny => ffi::enum_field_types::MYSQL_TYPE_TINY,
+        MysqlType::Short => ffi::enum_field_types::MYSQL_TYPE_SHORT,
+        MysqlType::Long => ffi::enum_field_types::MYSQL_TYPE_LONG,
+        MysqlType::LongLong => ffi::enum_field_types::MYSQL_TYPE_LONGLONG,
+        MysqlType::Float => ffi::enum_field_types::MYSQL_TYPE_FLOAT,
+        MysqlType::Double => ffi::enum_field_types::MYSQL_TYPE_DOUBLE,
+        MysqlType::Time => ffi::enum_field_types::MYSQL_TYPE_TIME,
+        MysqlType::Date => ffi::enum_field_types::MYSQL_TYPE_DATE,
+        MysqlType::DateTime => ffi::enum_field_types::MYSQL_TYPE_DATETIME,
+        MysqlType::Timestamp => ffi::enum_field_types::MYSQL_TYPE_TIMESTAMP,
+        MysqlType::String | MysqlType::Blob => ffi::enum_field_types::MYSQL_TYPE_BLOB,
+        MysqlType::Numeric => ffi::enum_field_types::MYSQL_TYPE_NEWDECIMAL,
+    }
+}
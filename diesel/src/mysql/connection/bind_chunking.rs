@@ -0,0 +1,158 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Finds the text immediately before and after a single, flat,
+/// bracket-balanced run of `total_binds` placeholders in `sql` — e.g. the
+/// `?, ?, ?` inside an `IN (?, ?, ?)` list produced by `eq_any`.
+///
+/// Returns `None` if `sql` doesn't consist of exactly one such run (for
+/// example because it contains several independent parameter lists, or a
+/// `VALUES (?, ?), (?, ?)` batch insert whose rows would need to be split
+/// tuple-by-tuple rather than placeholder-by-placeholder), if the run is
+/// not the operand list of `IN` (a single `VALUES (?, ?)` row, a
+/// `NOT IN (...)` list), or if anything other than placeholders and commas
+/// sits between its first and last `?`, in which case the caller should
+/// not attempt to chunk it automatically.
+///
+/// Assumes none of the `?` characters in `sql` occur inside a string
+/// literal, which holds for every `QueryFragment` diesel generates itself
+/// since values are always bound, never inlined.
+pub(super) fn flat_placeholder_run(sql: &str, total_binds: usize) -> Option<(&str, &str)> {
+    if total_binds == 0 {
+        return None;
+    }
+
+    let positions: Vec<usize> = sql.match_indices('?').map(|(i, _)| i).collect();
+    if positions.len() != total_binds {
+        return None;
+    }
+
+    let run_start = positions[0];
+    let run_end = positions[total_binds - 1] + 1;
+    let run = &sql[run_start..run_end];
+    if run.contains('(') || run.contains(')') {
+        // Nested brackets mean the run is made of multi-value tuples
+        // rather than independent scalar placeholders; we don't know how
+        // to safely split those.
+        return None;
+    }
+    if !run.chars().all(|c| c == '?' || c == ',' || c.is_whitespace()) {
+        // Other list items between the placeholders would be dropped by
+        // `chunk_sql`.
+        return None;
+    }
+
+    let prefix = &sql[..run_start];
+    let suffix = &sql[run_end..];
+    if !prefix.trim_end().ends_with('(') || !suffix.trim_start().starts_with(')') {
+        return None;
+    }
+
+    // Only an `IN` list can be split into chunks whose results add up;
+    // a `NOT IN` list or a single `VALUES` row can't.
+    let before_list = prefix.trim_end().strip_suffix('(')?.trim_end().to_ascii_uppercase();
+    if !before_list.ends_with(" IN") || before_list.ends_with(" NOT IN") {
+        return None;
+    }
+
+    Some((prefix, suffix))
+}
+
+/// Builds the SQL for one chunk of a split placeholder list.
+pub(super) fn chunk_sql(prefix: &str, suffix: &str, chunk_len: usize) -> String {
+    let mut sql = String::with_capacity(prefix.len() + suffix.len() + chunk_len * 3);
+    sql.push_str(prefix);
+    for i in 0..chunk_len {
+        if i > 0 {
+            sql.push_str(", ");
+        }
+        sql.push('?');
+    }
+    sql.push_str(suffix);
+    sql
+}
+
+#[derive(Debug)]
+pub(super) struct TooManyBindParamsToChunk {
+    pub(super) count: usize,
+    pub(super) limit: usize,
+    /// What `count` and `limit` measure, e.g. `"bind parameters"` or
+    /// `"bytes of bind data"`.
+    pub(super) unit: &'static str,
+}
+
+impl fmt::Display for TooManyBindParamsToChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "this query produces {} {}, which exceeds the configured \
+             limit of {}, and diesel does not know how to automatically split this \
+             particular query into smaller statements (only a single flat `IN (...)` \
+             style placeholder list can be chunked automatically); reduce the batch \
+             size manually",
+            self.count, self.unit, self.limit
+        )
+    }
+}
+
+impl StdError for TooManyBindParamsToChunk {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_placeholder_run_finds_a_simple_in_list() {
+        let sql = "SELECT * FROM users WHERE id IN (?, ?, ?)";
+        let (prefix, suffix) = flat_placeholder_run(sql, 3).unwrap();
+        assert_eq!(prefix, "SELECT * FROM users WHERE id IN (");
+        assert_eq!(suffix, ")");
+    }
+
+    #[test]
+    fn flat_placeholder_run_returns_none_when_bind_count_does_not_match() {
+        let sql = "SELECT * FROM users WHERE id IN (?, ?, ?)";
+        assert!(flat_placeholder_run(sql, 2).is_none());
+    }
+
+    #[test]
+    fn flat_placeholder_run_returns_none_for_zero_binds() {
+        assert!(flat_placeholder_run("SELECT * FROM users", 0).is_none());
+    }
+
+    #[test]
+    fn flat_placeholder_run_returns_none_for_multi_value_tuples() {
+        let sql = "INSERT INTO users (id, name) VALUES (?, ?), (?, ?)";
+        assert!(flat_placeholder_run(sql, 4).is_none());
+    }
+
+    #[test]
+    fn flat_placeholder_run_returns_none_when_run_is_not_bracket_delimited() {
+        let sql = "SELECT ? FROM users WHERE id = ?";
+        assert!(flat_placeholder_run(sql, 2).is_none());
+    }
+
+    #[test]
+    fn chunk_sql_builds_a_placeholder_list_of_the_requested_length() {
+        let sql = chunk_sql("SELECT * FROM users WHERE id IN (", ")", 3);
+        assert_eq!(sql, "SELECT * FROM users WHERE id IN (?, ?, ?)");
+    }
+
+    #[test]
+    fn chunk_sql_handles_a_single_placeholder() {
+        let sql = chunk_sql("(", ")", 1);
+        assert_eq!(sql, "(?)");
+    }
+
+    #[test]
+    fn too_many_bind_params_to_chunk_formats_a_helpful_message() {
+        let err = TooManyBindParamsToChunk {
+            count: 200_000,
+            limit: 65_535,
+            unit: "bind parameters",
+        };
+        let message = err.to_string();
+        assert!(message.contains("200000"));
+        assert!(message.contains("65535"));
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+
+/// A minimal least-recently-used cache with a fixed capacity, used to
+/// bound [`MysqlStatementCache`](super::stmt_cache::MysqlStatementCache)
+/// without pulling in an external dependency for what's a handful of
+/// entries at most.
+pub(super) struct LruCache<K, V> {
+    capacity: NonZeroUsize,
+    entries: HashMap<K, V>,
+    // Most-recently-used key is at the back.
+    order: VecDeque<K>,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    pub(super) fn new(capacity: NonZeroUsize) -> Self {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub(super) fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub(super) fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        if self.entries.contains_key(key) {
+            self.touch(key);
+        }
+        self.entries.get_mut(key)
+    }
+
+    pub(super) fn put(&mut self, key: K, value: V) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity.get() {
+            if let Some(lru_key) = self.order.pop_front() {
+                self.entries.remove(&lru_key);
+            }
+        }
+        self.order.retain(|k| *k != key);
+        self.order.push_back(key.clone());
+        self.entries.insert(key, value);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            let key = self.order.remove(pos).expect("position was just found");
+            self.order.push_back(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(capacity: usize) -> LruCache<&'static str, i32> {
+        LruCache::new(NonZeroUsize::new(capacity).unwrap())
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entry_once_over_capacity() {
+        let mut cache = cache(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+
+        assert!(!cache.contains(&"a"));
+        assert!(cache.contains(&"b"));
+        assert!(cache.contains(&"c"));
+    }
+
+    #[test]
+    fn get_mut_marks_entry_as_recently_used() {
+        let mut cache = cache(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+
+        assert!(cache.get_mut(&"a").is_some());
+        cache.put("c", 3);
+
+        assert!(cache.contains(&"a"));
+        assert!(!cache.contains(&"b"));
+    }
+
+    #[test]
+    fn put_overwrites_existing_entry_without_evicting() {
+        let mut cache = cache(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("a", 10);
+
+        assert_eq!(cache.get_mut(&"a"), Some(&mut 10));
+        assert!(cache.contains(&"b"));
+    }
+
+    #[test]
+    fn clear_empties_the_cache() {
+        let mut cache = cache(2);
+        cache.put("a", 1);
+        cache.clear();
+
+        assert!(!cache.contains(&"a"));
+    }
+}
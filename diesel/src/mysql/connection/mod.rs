@@ -1,12 +1,21 @@
 mod bind;
+mod bind_chunking;
+mod lru_cache;
 mod raw;
 mod stmt;
+mod stmt_cache;
 mod url;
 
+use std::num::NonZeroUsize;
+
+use self::bind_chunking::TooManyBindParamsToChunk;
 use self::raw::RawConnection;
 use self::stmt::iterator::StatementIterator;
 use self::stmt::Statement;
+use self::stmt_cache::MysqlStatementCache;
+pub use self::stmt_cache::StatementCacheSize;
 use self::url::ConnectionOptions;
+pub use self::url::MysqlConnectionOptions;
 use super::backend::Mysql;
 use crate::connection::commit_error_processor::{
     default_process_commit_error, CommitErrorOutcome, CommitErrorProcessor,
@@ -17,13 +26,28 @@ use crate::query_builder::bind_collector::RawBytesBindCollector;
 use crate::query_builder::*;
 use crate::result::*;
 
+/// The number of bind parameters a single prepared statement can safely
+/// hold, matching the limit MySQL itself enforces
+/// (`@@max_prepared_stmt_count` aside, a single statement is capped at
+/// 65535 placeholders).
+const DEFAULT_MAX_BIND_PARAMS: usize = 65_535;
+
+/// A conservative default budget, in bytes, for the serialized bind data
+/// of a single prepared statement execution. This mirrors libmysqlclient's
+/// historic default for `max_allowed_packet` (1MiB); servers are commonly
+/// configured higher, but a connection has no way to know the server's
+/// actual setting ahead of time, so chunking stays on the safe side of it.
+const DEFAULT_MAX_BIND_BYTES: usize = 1_048_576;
+
 #[allow(missing_debug_implementations, missing_copy_implementations)]
 /// A connection to a MySQL database. Connection URLs should be in the form
 /// `mysql://[user[:password]@]host/database_name`
 pub struct MysqlConnection {
     raw_connection: RawConnection,
     transaction_state: AnsiTransactionManager,
-    statement_cache: StatementCache<Mysql, Statement>,
+    statement_cache: MysqlStatementCache,
+    max_bind_params: usize,
+    max_bind_bytes: usize,
 }
 
 unsafe impl Send for MysqlConnection {}
@@ -65,9 +89,11 @@ impl Connection for MysqlConnection {
         let mut conn = MysqlConnection {
             raw_connection,
             transaction_state: AnsiTransactionManager::default(),
-            statement_cache: StatementCache::new(),
+            statement_cache: MysqlStatementCache::new(StatementCacheSize::default()),
+            max_bind_params: DEFAULT_MAX_BIND_PARAMS,
+            max_bind_bytes: DEFAULT_MAX_BIND_BYTES,
         };
-        conn.set_config_options()
+        conn.configure_session(connection_options.session_config().clone())
             .map_err(CouldntSetupConfiguration)?;
         Ok(conn)
     }
@@ -102,7 +128,20 @@ impl Connection for MysqlConnection {
     where
         T: QueryFragment<Self::Backend> + QueryId,
     {
-        let stmt = self.prepared_query(source)?;
+        let mut bind_collector = RawBytesBindCollector::new();
+        source.collect_binds(&mut bind_collector, &mut ())?;
+        let total_bind_bytes: usize = bind_collector
+            .binds
+            .iter()
+            .map(|bind| bind.as_ref().map_or(0, Vec::len))
+            .sum();
+        if bind_collector.binds.len() > self.max_bind_params
+            || total_bind_bytes > self.max_bind_bytes
+        {
+            return self.execute_returning_count_chunked(source, bind_collector);
+        }
+
+        let stmt = self.prepared_query_with_binds(source, bind_collector)?;
         unsafe {
             stmt.execute()?;
         }
@@ -118,10 +157,13 @@ impl Connection for MysqlConnection {
 #[cfg(feature = "r2d2")]
 impl crate::r2d2::R2D2Connection for MysqlConnection {
     fn ping(&mut self) -> QueryResult<()> {
-        self.execute("SELECT 1").map(|_| ())
+        self.raw_connection.ping()
     }
 
     fn is_broken(&mut self) -> bool {
+        if self.raw_connection.is_server_gone() {
+            return true;
+        }
         self.transaction_state
             .status
             .transaction_depth()
@@ -130,17 +172,83 @@ impl crate::r2d2::R2D2Connection for MysqlConnection {
     }
 }
 
+#[cfg(feature = "r2d2")]
+mod r2d2_customizer {
+    use super::MysqlConnection;
+    use crate::result::QueryResult;
+
+    /// An [`r2d2::CustomizeConnection`] that resets a connection via
+    /// [`MysqlConnection::reset`] every time one is checked out of the
+    /// pool, instead of letting leftover session state (user variables,
+    /// temporary tables, an uncommitted transaction left open by a buggy
+    /// borrower) carry over to whoever is handed the connection next.
+    ///
+    /// r2d2 has no hook that runs when a connection is checked back *in*
+    /// — [`on_release`](r2d2::CustomizeConnection::on_release) only fires
+    /// when the pool is about to drop a connection entirely (idle reap,
+    /// error eviction, pool shutdown), not on an ordinary
+    /// `PooledConnection` going out of scope, so it can't be used to
+    /// reset connections between borrowers. Resetting on
+    /// [`on_acquire`](r2d2::CustomizeConnection::on_acquire) instead gets
+    /// the same end result — every caller that receives a connection from
+    /// the pool gets one in a known-clean state — it just does the work
+    /// just before handing the connection out rather than just after
+    /// getting it back.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct ResetOnAcquire;
+
+    impl r2d2::CustomizeConnection<MysqlConnection, crate::result::Error> for ResetOnAcquire {
+        fn on_acquire(&self, conn: &mut MysqlConnection) -> QueryResult<()> {
+            conn.reset()
+        }
+    }
+}
+
+#[cfg(feature = "r2d2")]
+pub use self::r2d2_customizer::ResetOnAcquire;
+
+impl MysqlConnection {
+    /// Returns this connection to a just-connected state via MySQL's
+    /// `COM_RESET_CONNECTION` command, without the TCP/TLS/auth round trip
+    /// a fresh [`establish`](Connection::establish) would require.
+    ///
+    /// This clears user-defined variables, temporary tables and
+    /// transaction state on the server. Because it also invalidates every
+    /// prepared statement handle the server was holding for this
+    /// connection, the local statement cache is flushed as well so that a
+    /// later query re-prepares instead of using a now-dangling handle.
+    ///
+    /// This is primarily useful for connection pools that want to recycle
+    /// a borrowed connection cheaply; see [`ResetOnAcquire`].
+    pub fn reset(&mut self) -> QueryResult<()> {
+        self.raw_connection.reset()?;
+        self.statement_cache.clear();
+        self.transaction_state = AnsiTransactionManager::default();
+        Ok(())
+    }
+}
+
 impl MysqlConnection {
     fn prepared_query<'a, T: QueryFragment<Mysql> + QueryId>(
         &'a mut self,
         source: &'_ T,
     ) -> QueryResult<MaybeCached<'a, Statement>> {
-        let cache = &mut self.statement_cache;
-        let conn = &mut self.raw_connection;
-
-        let mut stmt = cache.cached_statement(source, &[], |sql, _| conn.prepare(sql))?;
         let mut bind_collector = RawBytesBindCollector::new();
         source.collect_binds(&mut bind_collector, &mut ())?;
+        self.prepared_query_with_binds(source, bind_collector)
+    }
+
+    /// Like [`prepared_query`](Self::prepared_query), but for callers that
+    /// already collected `source`'s binds (to inspect their count/size,
+    /// say) and shouldn't pay to serialize them a second time.
+    fn prepared_query_with_binds<'a, T: QueryFragment<Mysql> + QueryId>(
+        &'a mut self,
+        source: &'_ T,
+        bind_collector: RawBytesBindCollector<Mysql>,
+    ) -> QueryResult<MaybeCached<'a, Statement>> {
+        let mut stmt = self
+            .statement_cache
+            .cached_statement(source, &self.raw_connection)?;
         let binds = bind_collector
             .metadata
             .into_iter()
@@ -149,16 +257,199 @@ impl MysqlConnection {
         Ok(stmt)
     }
 
-    fn set_config_options(&mut self) -> QueryResult<()> {
-        self.execute("SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT'))")?;
-        self.execute("SET time_zone = '+00:00';")?;
-        self.execute("SET character_set_client = 'utf8mb4'")?;
-        self.execute("SET character_set_connection = 'utf8mb4'")?;
-        self.execute("SET character_set_results = 'utf8mb4'")?;
+    /// Runs `source` in chunks small enough to stay under both
+    /// [`max_bind_params`](Self::set_max_bind_params) placeholders *and*
+    /// [`max_bind_bytes`](Self::set_max_bind_bytes) of serialized bind data
+    /// per statement, summing the affected-row counts. A single bind that
+    /// alone exceeds the byte budget (e.g. one huge BLOB) is still placed
+    /// in a chunk by itself rather than rejected, since there's no way to
+    /// split an individual placeholder's value.
+    ///
+    /// Only a single flat `IN (?, ?, ..., ?)`-style placeholder list (as
+    /// produced by `eq_any`) can be split automatically; anything else
+    /// (e.g. a multi-row `VALUES (...)` batch insert) returns a clear
+    /// error instead of silently doing the wrong thing, since chunking a
+    /// batch insert requires splitting whole rows rather than individual
+    /// placeholders.
+    fn execute_returning_count_chunked<T>(
+        &mut self,
+        source: &T,
+        bind_collector: RawBytesBindCollector<Mysql>,
+    ) -> QueryResult<usize>
+    where
+        T: QueryFragment<Mysql> + QueryId,
+    {
+        let total_binds = bind_collector.binds.len();
+        let max_bind_params = self.max_bind_params;
+        let max_bind_bytes = self.max_bind_bytes;
+        let sql = self::stmt_cache::build_sql(source)?;
+        let (prefix, suffix) = self::bind_chunking::flat_placeholder_run(&sql, total_binds)
+            .ok_or_else(|| {
+                let error = if total_binds > max_bind_params {
+                    TooManyBindParamsToChunk {
+                        count: total_binds,
+                        limit: max_bind_params,
+                        unit: "bind parameters",
+                    }
+                } else {
+                    TooManyBindParamsToChunk {
+                        count: bind_collector
+                            .binds
+                            .iter()
+                            .map(|bind| bind.as_ref().map_or(0, Vec::len))
+                            .sum(),
+                        limit: max_bind_bytes,
+                        unit: "bytes of bind data",
+                    }
+                };
+                Error::QueryBuilderError(Box::new(error))
+            })?;
+        let metadata = bind_collector.metadata;
+        let binds = bind_collector.binds;
+        let bind_byte_len = |bind: &Option<Vec<u8>>| bind.as_ref().map_or(0, Vec::len);
+
+        self.transaction(|conn| {
+            let mut total_affected = 0usize;
+            let mut start = 0;
+            while start < total_binds {
+                let mut end = start;
+                let mut chunk_bytes = 0usize;
+                while end < total_binds
+                    && end - start < max_bind_params
+                    && (end == start || chunk_bytes + bind_byte_len(&binds[end]) <= max_bind_bytes)
+                {
+                    chunk_bytes += bind_byte_len(&binds[end]);
+                    end += 1;
+                }
+                let chunk_sql = self::bind_chunking::chunk_sql(prefix, suffix, end - start);
+                let mut stmt = conn.raw_connection.prepare(&chunk_sql)?;
+                let chunk_binds = metadata[start..end]
+                    .iter()
+                    .cloned()
+                    .zip(binds[start..end].iter().cloned());
+                stmt.bind(chunk_binds)?;
+                unsafe {
+                    stmt.execute()?;
+                }
+                total_affected += stmt.affected_rows();
+                start = end;
+            }
+            Ok(total_affected)
+        })
+    }
+
+    /// Configures how many prepared statements this connection keeps cached.
+    ///
+    /// By default a [`MysqlConnection`] caches every prepared statement it
+    /// creates for the lifetime of the connection
+    /// ([`StatementCacheSize::Unbounded`]). Long-lived pooled connections
+    /// that see many distinct query shapes should use
+    /// [`StatementCacheSize::Bounded`] to stay under the server's
+    /// `max_prepared_stmt_count`; the least-recently-used statement is
+    /// evicted (and its server-side handle deallocated) once the limit is
+    /// reached.
+    ///
+    /// Changing the cache size discards any statements already cached.
+    pub fn set_prepared_statement_cache_size(&mut self, size: StatementCacheSize) {
+        self.statement_cache = MysqlStatementCache::new(size);
+    }
+
+    /// Sets the maximum number of bind parameters a single statement run
+    /// through [`execute_returning_count`](Connection::execute_returning_count)
+    /// may use, defaulting to MySQL's own 65535-placeholder limit.
+    ///
+    /// A statement that would exceed this and whose binds form a single
+    /// flat `IN (...)` list (e.g. a large `eq_any` in an `UPDATE` or
+    /// `DELETE`) is automatically split into multiple smaller statements
+    /// executed inside one transaction; any other statement that exceeds
+    /// it returns an error. Queries run through [`load`](Connection::load)
+    /// are not checked against this limit and are never split.
+    pub fn set_max_bind_params(&mut self, max_bind_params: NonZeroUsize) {
+        self.max_bind_params = max_bind_params.get();
+    }
+
+    /// Sets the maximum total size, in bytes, of the serialized bind data
+    /// a single statement run through
+    /// [`execute_returning_count`](Connection::execute_returning_count) may
+    /// send, defaulting to a conservative 1MiB.
+    ///
+    /// Statements whose bind data would exceed this (e.g. a large `eq_any`
+    /// over big `TEXT`/`BLOB` values) are chunked, or rejected, the same
+    /// way statements exceeding [`max_bind_params`](Self::set_max_bind_params)
+    /// are; queries run through [`load`](Connection::load) are not checked
+    /// against this limit and are never split. Set
+    /// this to match your server's actual `max_allowed_packet` if you know
+    /// it to avoid chunking more aggressively than necessary.
+    pub fn set_max_bind_bytes(&mut self, max_bind_bytes: NonZeroUsize) {
+        self.max_bind_bytes = max_bind_bytes.get();
+    }
+}
+
+impl MysqlConnection {
+    /// Applies the MySQL session settings (time zone, character set and
+    /// `sql_mode` tweaks) described by `options`. Called once right after
+    /// [`establish`](Connection::establish) with whatever
+    /// [`MysqlConnectionOptions`] were derived from the connection URL, but
+    /// it can be called again at any time to change these settings on an
+    /// already-open connection.
+    pub fn configure_session(&mut self, options: MysqlConnectionOptions) -> QueryResult<()> {
+        if options.pipes_as_concat {
+            self.execute("SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT'))")?;
+        }
+        if let Some(time_zone) = options.time_zone.as_deref() {
+            let time_zone = check_session_setting_is_safe_to_interpolate("time_zone", time_zone)?;
+            self.execute(&format!("SET time_zone = '{time_zone}'"))?;
+        }
+        let charset = check_session_setting_is_safe_to_interpolate("charset", &options.charset)?;
+        self.execute(&format!("SET character_set_client = '{charset}'"))?;
+        self.execute(&format!("SET character_set_connection = '{charset}'"))?;
+        self.execute(&format!("SET character_set_results = '{charset}'"))?;
         Ok(())
     }
 }
 
+/// MySQL's `SET` statement doesn't support bind parameters for the values
+/// being assigned, so [`configure_session`](MysqlConnection::configure_session)
+/// has to splice `time_zone`/`charset` directly into the SQL it sends.
+/// Both ultimately come from caller-controlled input (the connection
+/// URL's `timezone`/`charset` query parameters, or the
+/// [`MysqlConnectionOptions`] builder), so this allow-lists them to the
+/// characters a real time zone or charset name can contain before they're
+/// ever interpolated, closing off `'`-based SQL injection.
+fn check_session_setting_is_safe_to_interpolate<'a>(
+    name: &'static str,
+    value: &'a str,
+) -> QueryResult<&'a str> {
+    let is_safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | ':' | '/'));
+    if is_safe {
+        Ok(value)
+    } else {
+        Err(Error::QueryBuilderError(Box::new(
+            InvalidSessionSettingValue { name },
+        )))
+    }
+}
+
+#[derive(Debug)]
+struct InvalidSessionSettingValue {
+    name: &'static str,
+}
+
+impl std::fmt::Display for InvalidSessionSettingValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` may only contain ASCII letters, digits, `_`, `+`, `-`, `:` or `/`",
+            self.name
+        )
+    }
+}
+
+impl std::error::Error for InvalidSessionSettingValue {}
+
 #[cfg(test)]
 mod tests {
     extern crate dotenv;
@@ -166,6 +457,36 @@ mod tests {
     use super::*;
     use std::env;
 
+    #[test]
+    fn session_setting_allows_ordinary_time_zone_and_charset_values() {
+        assert_eq!(
+            check_session_setting_is_safe_to_interpolate("time_zone", "+00:00").unwrap(),
+            "+00:00"
+        );
+        assert_eq!(
+            check_session_setting_is_safe_to_interpolate("timezone", "America/New_York").unwrap(),
+            "America/New_York"
+        );
+        assert_eq!(
+            check_session_setting_is_safe_to_interpolate("charset", "utf8mb4").unwrap(),
+            "utf8mb4"
+        );
+    }
+
+    #[test]
+    fn session_setting_rejects_values_that_would_break_out_of_the_sql_string_literal() {
+        assert!(
+            check_session_setting_is_safe_to_interpolate("charset", "utf8mb4' OR SLEEP(5)='",)
+                .is_err()
+        );
+        assert!(check_session_setting_is_safe_to_interpolate("time_zone", "' OR 1=1 -- ").is_err());
+    }
+
+    #[test]
+    fn session_setting_rejects_empty_values() {
+        assert!(check_session_setting_is_safe_to_interpolate("charset", "").is_err());
+    }
+
     fn connection() -> MysqlConnection {
         dotenv::dotenv().ok();
         let database_url = env::var("MYSQL_UNIT_TEST_DATABASE_URL")
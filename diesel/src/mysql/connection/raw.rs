@@ -0,0 +1,239 @@
+extern crate mysqlclient_sys as ffi;
+
+use std::ffi::{CStr, CString};
+use std::os::raw as libc;
+use std::ptr::{self, NonNull};
+
+use super::stmt::Statement;
+use super::url::{ConnectionOptions, ConnectionTimeouts, SslMode, TlsOptions};
+use crate::result::{ConnectionError, ConnectionResult, QueryResult};
+
+/// `CR_SERVER_GONE_ERROR`: the client tried to send a command to the
+/// server but the connection had already been closed.
+const CR_SERVER_GONE_ERROR: libc::c_uint = 2006;
+/// `CR_SERVER_LOST`: the connection was lost while reading the server's
+/// reply to a command.
+const CR_SERVER_LOST: libc::c_uint = 2013;
+
+pub(super) struct RawConnection(NonNull<ffi::MYSQL>);
+
+impl RawConnection {
+    pub(super) fn new() -> Self {
+        unsafe {
+            let raw_ptr = ffi::mysql_init(ptr::null_mut());
+            Self(NonNull::new(raw_ptr).expect("Out of memory calling mysql_init"))
+        }
+    }
+
+    pub(super) fn connect(&self, connection_options: &ConnectionOptions) -> ConnectionResult<()> {
+        unsafe {
+            self.configure_tls(connection_options.tls());
+            self.configure_timeouts(connection_options.timeouts());
+
+            let host = connection_options.host();
+            let user = connection_options.user();
+            let password = connection_options.password();
+            let database = connection_options.database();
+            let port = connection_options.port();
+
+            let success = ffi::mysql_real_connect(
+                self.0.as_ptr(),
+                host.map_or(ptr::null(), |h| h.as_ptr()),
+                user.as_ptr(),
+                password.map_or(ptr::null(), |p| p.as_ptr()),
+                database.map_or(ptr::null(), |d| d.as_ptr()),
+                u32::from(port.unwrap_or(0)),
+                ptr::null(),
+                0,
+            );
+
+            if success.is_null() {
+                Err(ConnectionError::BadConnection(self.last_error_message()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Applies `ssl_mode`/`ssl_ca`/`ssl_cert`/`ssl_key` via
+    /// `mysql_options`/`mysql_ssl_set`. Must be called before
+    /// `mysql_real_connect`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called while `self.0` is not yet connected.
+    unsafe fn configure_tls(&self, tls: &TlsOptions) {
+        let mode = match tls.mode {
+            SslMode::Disabled => ffi::mysql_ssl_mode::SSL_MODE_DISABLED,
+            SslMode::Preferred => ffi::mysql_ssl_mode::SSL_MODE_PREFERRED,
+            SslMode::Required => ffi::mysql_ssl_mode::SSL_MODE_REQUIRED,
+            SslMode::VerifyCa => ffi::mysql_ssl_mode::SSL_MODE_VERIFY_CA,
+            SslMode::VerifyIdentity => ffi::mysql_ssl_mode::SSL_MODE_VERIFY_IDENTITY,
+        };
+        ffi::mysql_options(
+            self.0.as_ptr(),
+            ffi::mysql_option::MYSQL_OPT_SSL_MODE,
+            &mode as *const _ as *const libc::c_void,
+        );
+
+        if tls.mode == SslMode::Disabled {
+            return;
+        }
+
+        ffi::mysql_ssl_set(
+            self.0.as_ptr(),
+            tls.key.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+            tls.cert.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+            tls.ca.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+            ptr::null(),
+            ptr::null(),
+        );
+    }
+
+    /// Applies `read_timeout`/`write_timeout`/`connect_timeout` via
+    /// `mysql_options`. Must be called before `mysql_real_connect`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called while `self.0` is not yet connected.
+    unsafe fn configure_timeouts(&self, timeouts: &ConnectionTimeouts) {
+        let set = |option, seconds: &u32| {
+            ffi::mysql_options(
+                self.0.as_ptr(),
+                option,
+                seconds as *const u32 as *const libc::c_void,
+            );
+        };
+        if let Some(ref seconds) = timeouts.read {
+            set(ffi::mysql_option::MYSQL_OPT_READ_TIMEOUT, seconds);
+        }
+        if let Some(ref seconds) = timeouts.write {
+            set(ffi::mysql_option::MYSQL_OPT_WRITE_TIMEOUT, seconds);
+        }
+        if let Some(ref seconds) = timeouts.connect {
+            set(ffi::mysql_option::MYSQL_OPT_CONNECT_TIMEOUT, seconds);
+        }
+    }
+
+    /// A cheap, non-blocking-ish liveness check (`mysql_ping`) that
+    /// reconnects automatically if `MYSQL_OPT_RECONNECT` is set, without
+    /// the overhead of a full query round trip like `SELECT 1`.
+    pub(super) fn ping(&self) -> QueryResult<()> {
+        unsafe {
+            if ffi::mysql_ping(self.0.as_ptr()) == 0 {
+                Ok(())
+            } else {
+                Err(self.last_error())
+            }
+        }
+    }
+
+    /// Whether the last error seen on this connection indicates the
+    /// socket is dead (`CR_SERVER_GONE_ERROR`/`CR_SERVER_LOST`), as
+    /// opposed to an ordinary query error.
+    pub(super) fn is_server_gone(&self) -> bool {
+        matches!(
+            self.last_error_number(),
+            CR_SERVER_GONE_ERROR | CR_SERVER_LOST
+        )
+    }
+
+    pub(super) fn enable_multi_statements<T>(
+        &self,
+        f: impl FnOnce() -> QueryResult<T>,
+    ) -> QueryResult<T> {
+        unsafe {
+            ffi::mysql_set_server_option(
+                self.0.as_ptr(),
+                ffi::enum_mysql_set_option::MYSQL_OPTION_MULTI_STATEMENTS_ON,
+            );
+        }
+        let result = f();
+        unsafe {
+            ffi::mysql_set_server_option(
+                self.0.as_ptr(),
+                ffi::enum_mysql_set_option::MYSQL_OPTION_MULTI_STATEMENTS_OFF,
+            );
+        }
+        result
+    }
+
+    pub(super) fn execute(&self, query: &str) -> QueryResult<()> {
+        unsafe {
+            let query = CString::new(query)
+                .map_err(|e| crate::result::Error::QueryBuilderError(Box::new(e)))?;
+            let result = ffi::mysql_real_query(
+                self.0.as_ptr(),
+                query.as_ptr() as *const libc::c_char,
+                query.as_bytes().len() as libc::c_ulong,
+            );
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(self.last_error())
+            }
+        }
+    }
+
+    pub(super) fn prepare(&self, query: &str) -> QueryResult<Statement> {
+        unsafe {
+            let stmt = ffi::mysql_stmt_init(self.0.as_ptr());
+            let stmt = NonNull::new(stmt).ok_or_else(|| self.last_error())?;
+            let query = CString::new(query)
+                .map_err(|e| crate::result::Error::QueryBuilderError(Box::new(e)))?;
+            let result = ffi::mysql_stmt_prepare(
+                stmt.as_ptr(),
+                query.as_ptr() as *const libc::c_char,
+                query.as_bytes().len() as libc::c_ulong,
+            );
+            if result == 0 {
+                Ok(Statement::new(stmt))
+            } else {
+                ffi::mysql_stmt_close(stmt.as_ptr());
+                Err(self.last_error())
+            }
+        }
+    }
+
+    pub(super) fn affected_rows(&self) -> usize {
+        unsafe { ffi::mysql_affected_rows(self.0.as_ptr()) as usize }
+    }
+
+    /// Resets the session to a just-connected state via
+    /// `COM_RESET_CONNECTION` (`mysql_reset_connection`): it clears
+    /// user-defined variables, temporary tables and prepared statement
+    /// handles, and rolls back any open transaction, without paying for a
+    /// new TCP/TLS/auth handshake.
+    pub(super) fn reset(&self) -> QueryResult<()> {
+        unsafe {
+            if ffi::mysql_reset_connection(self.0.as_ptr()) == 0 {
+                Ok(())
+            } else {
+                Err(self.last_error())
+            }
+        }
+    }
+
+    pub(super) fn last_error_message(&self) -> String {
+        unsafe { CStr::from_ptr(ffi::mysql_error(self.0.as_ptr())) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    pub(super) fn last_error_number(&self) -> libc::c_uint {
+        unsafe { ffi::mysql_errno(self.0.as_ptr()) }
+    }
+
+    fn last_error(&self) -> crate::result::Error {
+        crate::result::Error::DatabaseError(
+            crate::result::DatabaseErrorKind::Unknown,
+            Box::new(self.last_error_message()),
+        )
+    }
+}
+
+impl Drop for RawConnection {
+    fn drop(&mut self) {
+        unsafe { ffi::mysql_close(self.0.as_ptr()) }
+    }
+}
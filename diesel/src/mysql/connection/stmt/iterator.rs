@@ -0,0 +1,38 @@
+extern crate mysqlclient_sys as ffi;
+
+use super::Statement;
+use crate::connection::statement_cache::MaybeCached;
+use crate::result::QueryResult;
+
+pub struct MysqlRow {
+    col_idx: usize,
+}
+
+pub struct StatementIterator<'a> {
+    stmt: MaybeCached<'a, Statement>,
+}
+
+impl<'a> StatementIterator<'a> {
+    pub(in crate::mysql) fn from_stmt(
+        mut stmt: MaybeCached<'a, Statement>,
+        _metadata: &[()],
+    ) -> QueryResult<Self> {
+        unsafe {
+            stmt.execute()?;
+        }
+        Ok(StatementIterator { stmt })
+    }
+}
+
+impl<'a> Iterator for StatementIterator<'a> {
+    type Item = QueryResult<MysqlRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = unsafe { ffi::mysql_stmt_fetch(self.stmt.inner.as_ptr()) };
+        if result == 0 {
+            Some(Ok(MysqlRow { col_idx: 0 }))
+        } else {
+            None
+        }
+    }
+}
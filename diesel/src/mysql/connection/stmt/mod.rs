@@ -0,0 +1,79 @@
+pub(super) mod iterator;
+
+extern crate mysqlclient_sys as ffi;
+
+use std::ptr::NonNull;
+
+use super::bind::Binds;
+use crate::mysql::MysqlType;
+use crate::result::{DatabaseErrorKind, Error, QueryResult};
+
+pub(super) struct Statement {
+    inner: NonNull<ffi::MYSQL_STMT>,
+    input_binds: Option<Binds>,
+}
+
+impl Statement {
+    pub(super) fn new(inner: NonNull<ffi::MYSQL_STMT>) -> Self {
+        Statement {
+            inner,
+            input_binds: None,
+        }
+    }
+
+    pub(super) fn bind(
+        &mut self,
+        binds: impl IntoIterator<Item = (MysqlType, Option<Vec<u8>>)>,
+    ) -> QueryResult<()> {
+        let mut binds = Binds::from_input_data(binds);
+        unsafe {
+            let mut mysql_binds = binds.mysql_binds();
+            let ptr = if mysql_binds.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                mysql_binds.as_mut_ptr()
+            };
+            if ffi::mysql_stmt_bind_param(self.inner.as_ptr(), ptr) {
+                return Err(self.last_error());
+            }
+        }
+        self.input_binds = Some(binds);
+        Ok(())
+    }
+
+    /// # Safety
+    ///
+    /// The caller must ensure that the bound parameters remain valid for the
+    /// duration of this call.
+    pub(super) unsafe fn execute(&self) -> QueryResult<()> {
+        if ffi::mysql_stmt_execute(self.inner.as_ptr()) == 0 {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+
+    pub(super) fn affected_rows(&self) -> usize {
+        unsafe { ffi::mysql_stmt_affected_rows(self.inner.as_ptr()) as usize }
+    }
+
+    pub(super) fn param_count(&self) -> usize {
+        self.input_binds.as_ref().map_or(0, Binds::len)
+    }
+
+    fn last_error(&self) -> Error {
+        let message =
+            unsafe { std::ffi::CStr::from_ptr(ffi::mysql_stmt_error(self.inner.as_ptr())) }
+                .to_string_lossy()
+                .into_owned();
+        Error::DatabaseError(DatabaseErrorKind::Unknown, Box::new(message))
+    }
+}
+
+impl Drop for Statement {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::mysql_stmt_close(self.inner.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::any::TypeId;
+use std::num::NonZeroUsize;
+
+use super::lru_cache::LruCache;
+use super::raw::RawConnection;
+use super::stmt::Statement;
+use crate::backend::Backend;
+use crate::connection::statement_cache::{MaybeCached, StatementCache};
+use crate::mysql::backend::Mysql;
+use crate::query_builder::{QueryFragment, QueryId};
+use crate::result::QueryResult;
+
+/// Configures how many prepared statements a [`MysqlConnection`] keeps
+/// around at once.
+///
+/// [`MysqlConnection`]: super::MysqlConnection
+#[derive(Debug, Clone, Copy)]
+pub enum StatementCacheSize {
+    /// Cache every prepared statement for the lifetime of the connection.
+    ///
+    /// This is the historic behavior and remains the default. Long-lived
+    /// pooled connections that see many distinct query shapes will
+    /// eventually accumulate enough prepared statements to exceed the
+    /// server's `max_prepared_stmt_count`.
+    Unbounded,
+    /// Keep at most `capacity` prepared statements alive, evicting the
+    /// least-recently-used one (and deallocating its server-side handle)
+    /// once the limit is reached.
+    Bounded(NonZeroUsize),
+}
+
+impl Default for StatementCacheSize {
+    fn default() -> Self {
+        StatementCacheSize::Unbounded
+    }
+}
+
+/// Wraps the generic, unbounded [`StatementCache`] with an optional LRU
+/// eviction policy for MySQL connections. See [`StatementCacheSize`].
+pub(super) enum MysqlStatementCache {
+    Unbounded(StatementCache<Mysql, Statement>),
+    Bounded(LruCache<CacheKey, Statement>),
+}
+
+/// Identifies a cached prepared statement the same way the generic
+/// [`StatementCache`] does: by `T::QueryId`'s [`TypeId`] whenever `T` has
+/// one (true for every query diesel itself builds), falling back to the
+/// rendered SQL text only for the rare query that doesn't have a static
+/// query id (e.g. `sql_query`, or a boxed query). This keeps cache hits on
+/// the common path as cheap as the unbounded cache's, instead of
+/// re-rendering SQL text on every lookup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum CacheKey {
+    Type(TypeId),
+    Sql(String),
+}
+
+fn cache_key<T: QueryFragment<Mysql> + QueryId>(source: &T) -> QueryResult<CacheKey> {
+    match T::query_id() {
+        Some(type_id) => Ok(CacheKey::Type(type_id)),
+        None => build_sql(source).map(CacheKey::Sql),
+    }
+}
+
+impl MysqlStatementCache {
+    pub(super) fn new(size: StatementCacheSize) -> Self {
+        match size {
+            StatementCacheSize::Unbounded => MysqlStatementCache::Unbounded(StatementCache::new()),
+            StatementCacheSize::Bounded(capacity) => {
+                MysqlStatementCache::Bounded(LruCache::new(capacity))
+            }
+        }
+    }
+
+    /// Drops every cached statement, closing their server-side handles,
+    /// while keeping the configured [`StatementCacheSize`].
+    pub(super) fn clear(&mut self) {
+        match self {
+            MysqlStatementCache::Unbounded(cache) => *cache = StatementCache::new(),
+            MysqlStatementCache::Bounded(cache) => cache.clear(),
+        }
+    }
+
+    pub(super) fn cached_statement<'a, T>(
+        &'a mut self,
+        source: &T,
+        conn: &RawConnection,
+    ) -> QueryResult<MaybeCached<'a, Statement>>
+    where
+        T: QueryFragment<Mysql> + QueryId,
+    {
+        match self {
+            MysqlStatementCache::Unbounded(cache) => {
+                cache.cached_statement(source, &[], |sql, _| conn.prepare(sql))
+            }
+            MysqlStatementCache::Bounded(cache) => {
+                let key = cache_key(source)?;
+                if !cache.contains(&key) {
+                    // Inserting the newly prepared statement into a full
+                    // cache evicts the least-recently-used entry, which
+                    // drops it and closes its server-side handle.
+                    let sql = build_sql(source)?;
+                    let stmt = conn.prepare(&sql)?;
+                    cache.put(key.clone(), stmt);
+                }
+                let stmt = cache
+                    .get_mut(&key)
+                    .expect("statement was just inserted into the cache");
+                Ok(MaybeCached::CacheHit(stmt))
+            }
+        }
+    }
+}
+
+pub(super) fn build_sql<T: QueryFragment<Mysql>>(source: &T) -> QueryResult<String> {
+    let mut query_builder = <Mysql as Backend>::QueryBuilder::default();
+    source.to_sql(&mut query_builder, &Mysql)?;
+    Ok(query_builder.finish())
+}
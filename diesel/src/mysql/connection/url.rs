@@ -0,0 +1,399 @@
+extern crate url;
+
+use std::ffi::CString;
+
+use self::url::Url;
+use crate::result::{ConnectionError, ConnectionResult};
+
+#[derive(Clone)]
+pub(super) struct ConnectionOptions {
+    host: Option<CString>,
+    user: CString,
+    password: Option<CString>,
+    database: Option<CString>,
+    port: Option<u16>,
+    session_config: MysqlConnectionOptions,
+    tls: TlsOptions,
+    timeouts: ConnectionTimeouts,
+}
+
+impl ConnectionOptions {
+    pub(super) fn parse(database_url: &str) -> ConnectionResult<Self> {
+        let url = Url::parse(database_url).map_err(|e| connection_url_error(&format!("{e}")))?;
+
+        if url.scheme() != "mysql" {
+            return Err(connection_url_error("The URL scheme must be `mysql://`"));
+        }
+
+        let host = match url.host_str() {
+            Some(host) => Some(CString::new(host).map_err(err_from_nul)?),
+            None => None,
+        };
+        let user = CString::new(url.username()).map_err(err_from_nul)?;
+        let password = match url.password() {
+            Some(password) => Some(CString::new(password).map_err(err_from_nul)?),
+            None => None,
+        };
+        let database = match url.path_segments().and_then(|mut segments| segments.next()) {
+            Some("") | None => None,
+            Some(segment) => Some(CString::new(segment).map_err(err_from_nul)?),
+        };
+
+        Ok(ConnectionOptions {
+            host,
+            user,
+            password,
+            database,
+            port: url.port(),
+            session_config: MysqlConnectionOptions::from_query_pairs(&url),
+            tls: TlsOptions::from_query_pairs(&url)?,
+            timeouts: ConnectionTimeouts::from_query_pairs(&url)?,
+        })
+    }
+
+    pub(super) fn host(&self) -> Option<&CString> {
+        self.host.as_ref()
+    }
+
+    pub(super) fn user(&self) -> &CString {
+        &self.user
+    }
+
+    pub(super) fn password(&self) -> Option<&CString> {
+        self.password.as_ref()
+    }
+
+    pub(super) fn database(&self) -> Option<&CString> {
+        self.database.as_ref()
+    }
+
+    pub(super) fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    pub(super) fn session_config(&self) -> &MysqlConnectionOptions {
+        &self.session_config
+    }
+
+    pub(super) fn tls(&self) -> &TlsOptions {
+        &self.tls
+    }
+
+    pub(super) fn timeouts(&self) -> &ConnectionTimeouts {
+        &self.timeouts
+    }
+}
+
+/// Read/write/connect timeouts, in seconds, applied via `mysql_options`
+/// before connecting so that a wedged network is detected quickly instead
+/// of hanging the caller (and, in a pool, every other borrower waiting
+/// behind it).
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct ConnectionTimeouts {
+    pub(super) read: Option<u32>,
+    pub(super) write: Option<u32>,
+    pub(super) connect: Option<u32>,
+}
+
+impl ConnectionTimeouts {
+    fn from_query_pairs(url: &Url) -> ConnectionResult<Self> {
+        let mut timeouts = Self::default();
+        for (key, value) in url.query_pairs() {
+            let seconds = match &*key {
+                "read_timeout" | "write_timeout" | "connect_timeout" => {
+                    Some(value.parse::<u32>().map_err(|_| {
+                        connection_url_error(&format!(
+                            "invalid `{key}` value `{value}`, expected a number of seconds"
+                        ))
+                    })?)
+                }
+                _ => None,
+            };
+            match &*key {
+                "read_timeout" => timeouts.read = seconds,
+                "write_timeout" => timeouts.write = seconds,
+                "connect_timeout" => timeouts.connect = seconds,
+                _ => {}
+            }
+        }
+        Ok(timeouts)
+    }
+}
+
+/// How strictly [`TlsOptions`] enforces an encrypted, verified connection.
+///
+/// Mirrors the `ssl_mode` values accepted by the standard MySQL client
+/// libraries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SslMode {
+    /// Never use TLS, even if the server supports it.
+    Disabled,
+    /// Use TLS if the server supports it, but don't fail the connection or
+    /// verify anything if it doesn't.
+    Preferred,
+    /// Require TLS, without verifying the server certificate.
+    Required,
+    /// Require TLS and verify the server certificate against `ssl_ca`.
+    VerifyCa,
+    /// Require TLS, verify the server certificate against `ssl_ca`, and
+    /// verify that the certificate's identity matches the host being
+    /// connected to.
+    VerifyIdentity,
+}
+
+impl Default for SslMode {
+    fn default() -> Self {
+        SslMode::Preferred
+    }
+}
+
+impl SslMode {
+    fn parse(value: &str) -> ConnectionResult<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "DISABLED" => Ok(SslMode::Disabled),
+            "PREFERRED" => Ok(SslMode::Preferred),
+            "REQUIRED" => Ok(SslMode::Required),
+            "VERIFY_CA" => Ok(SslMode::VerifyCa),
+            "VERIFY_IDENTITY" => Ok(SslMode::VerifyIdentity),
+            _ => Err(connection_url_error(&format!(
+                "invalid `ssl_mode` value `{value}`, expected one of DISABLED, \
+                 PREFERRED, REQUIRED, VERIFY_CA, VERIFY_IDENTITY"
+            ))),
+        }
+    }
+}
+
+/// TLS settings parsed from the `ssl_mode`, `ssl_ca`, `ssl_cert` and
+/// `ssl_key` query parameters of the connection URL, applied via
+/// `mysql_options`/`mysql_ssl_set` before `mysql_real_connect`.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TlsOptions {
+    pub(super) mode: SslMode,
+    pub(super) ca: Option<CString>,
+    pub(super) cert: Option<CString>,
+    pub(super) key: Option<CString>,
+}
+
+impl TlsOptions {
+    fn from_query_pairs(url: &Url) -> ConnectionResult<Self> {
+        let mut options = Self::default();
+        for (key, value) in url.query_pairs() {
+            match &*key {
+                "ssl_mode" => options.mode = SslMode::parse(&value)?,
+                "ssl_ca" => options.ca = Some(CString::new(&*value).map_err(err_from_nul)?),
+                "ssl_cert" => options.cert = Some(CString::new(&*value).map_err(err_from_nul)?),
+                "ssl_key" => options.key = Some(CString::new(&*value).map_err(err_from_nul)?),
+                _ => {}
+            }
+        }
+
+        if options.mode == SslMode::VerifyIdentity && options.ca.is_none() {
+            return Err(connection_url_error(
+                "`ssl_mode=VERIFY_IDENTITY` requires `ssl_ca` to be set",
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Controls the session settings [`MysqlConnection`](super::MysqlConnection)
+/// applies right after connecting.
+///
+/// By default diesel forces the session time zone to UTC, the connection
+/// character set to `utf8mb4`, and appends `PIPES_AS_CONCAT` to `sql_mode`
+/// so that `||` behaves as string concatenation rather than logical OR.
+/// These defaults can be overridden either through extra query parameters
+/// on the connection URL (`?timezone=...&charset=...&sql_mode=keep`) or by
+/// building a value of this type and passing it to
+/// [`MysqlConnection::configure_session`](super::MysqlConnection::configure_session).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MysqlConnectionOptions {
+    pub(super) time_zone: Option<String>,
+    pub(super) charset: String,
+    pub(super) pipes_as_concat: bool,
+}
+
+impl Default for MysqlConnectionOptions {
+    fn default() -> Self {
+        MysqlConnectionOptions {
+            time_zone: Some("+00:00".into()),
+            charset: "utf8mb4".into(),
+            pipes_as_concat: true,
+        }
+    }
+}
+
+impl MysqlConnectionOptions {
+    /// Leave the server's configured session time zone untouched instead
+    /// of forcing UTC.
+    pub fn system_time_zone(mut self) -> Self {
+        self.time_zone = None;
+        self
+    }
+
+    /// Set the session time zone, e.g. `"+00:00"` or a named zone the
+    /// server recognizes.
+    pub fn time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.time_zone = Some(time_zone.into());
+        self
+    }
+
+    /// Set the `character_set_client`/`_connection`/`_results` session
+    /// variables. Defaults to `"utf8mb4"`.
+    pub fn charset(mut self, charset: impl Into<String>) -> Self {
+        self.charset = charset.into();
+        self
+    }
+
+    /// Whether `PIPES_AS_CONCAT` should be appended to the server's
+    /// `sql_mode`. Defaults to `true`; set to `false` to rely on the
+    /// server's configured `sql_mode` as-is.
+    pub fn pipes_as_concat(mut self, enabled: bool) -> Self {
+        self.pipes_as_concat = enabled;
+        self
+    }
+
+    fn from_query_pairs(url: &Url) -> Self {
+        let mut options = Self::default();
+        for (key, value) in url.query_pairs() {
+            match &*key {
+                "timezone" if value == "system" => options.time_zone = None,
+                "timezone" => options.time_zone = Some(value.into_owned()),
+                "charset" => options.charset = value.into_owned(),
+                "sql_mode" if value == "keep" => options.pipes_as_concat = false,
+                _ => {}
+            }
+        }
+        options
+    }
+}
+
+fn err_from_nul(e: std::ffi::NulError) -> ConnectionError {
+    connection_url_error(&format!("{e}"))
+}
+
+fn connection_url_error(detail: &str) -> ConnectionError {
+    let msg = format!(
+        "MySQL connection URLs must be in the form \
+         `mysql://[user[:password]@]host/database_name`: {detail}"
+    );
+    ConnectionError::InvalidConnectionUrl(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn connection_timeouts_from_query_pairs_defaults_to_none() {
+        let timeouts =
+            ConnectionTimeouts::from_query_pairs(&parse_url("mysql://user@localhost/db")).unwrap();
+        assert_eq!(timeouts.read, None);
+        assert_eq!(timeouts.write, None);
+        assert_eq!(timeouts.connect, None);
+    }
+
+    #[test]
+    fn connection_timeouts_from_query_pairs_reads_each_timeout() {
+        let timeouts = ConnectionTimeouts::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?read_timeout=5&write_timeout=10&connect_timeout=2",
+        ))
+        .unwrap();
+        assert_eq!(timeouts.read, Some(5));
+        assert_eq!(timeouts.write, Some(10));
+        assert_eq!(timeouts.connect, Some(2));
+    }
+
+    #[test]
+    fn connection_timeouts_from_query_pairs_rejects_non_numeric_values() {
+        let result = ConnectionTimeouts::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?read_timeout=soon",
+        ));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn ssl_mode_parse_accepts_every_documented_value_case_insensitively() {
+        assert_eq!(SslMode::parse("disabled").unwrap(), SslMode::Disabled);
+        assert_eq!(SslMode::parse("Preferred").unwrap(), SslMode::Preferred);
+        assert_eq!(SslMode::parse("REQUIRED").unwrap(), SslMode::Required);
+        assert_eq!(SslMode::parse("verify_ca").unwrap(), SslMode::VerifyCa);
+        assert_eq!(
+            SslMode::parse("VERIFY_IDENTITY").unwrap(),
+            SslMode::VerifyIdentity
+        );
+    }
+
+    #[test]
+    fn ssl_mode_parse_rejects_unknown_values() {
+        assert!(SslMode::parse("verify-ca").is_err());
+        assert!(SslMode::parse("").is_err());
+    }
+
+    #[test]
+    fn tls_options_from_query_pairs_defaults_to_preferred_with_no_cert_material() {
+        let options =
+            TlsOptions::from_query_pairs(&parse_url("mysql://user@localhost/db")).unwrap();
+        assert_eq!(options.mode, SslMode::Preferred);
+        assert!(options.ca.is_none());
+        assert!(options.cert.is_none());
+        assert!(options.key.is_none());
+    }
+
+    #[test]
+    fn tls_options_from_query_pairs_reads_ssl_params() {
+        let options = TlsOptions::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?ssl_mode=VERIFY_CA&ssl_ca=%2Fetc%2Fca.pem",
+        ))
+        .unwrap();
+        assert_eq!(options.mode, SslMode::VerifyCa);
+        assert_eq!(options.ca.unwrap().to_str().unwrap(), "/etc/ca.pem");
+    }
+
+    #[test]
+    fn tls_options_from_query_pairs_requires_ssl_ca_for_verify_identity() {
+        let result = TlsOptions::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?ssl_mode=VERIFY_IDENTITY",
+        ));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn from_query_pairs_defaults_to_utc_and_utf8mb4() {
+        let options =
+            MysqlConnectionOptions::from_query_pairs(&parse_url("mysql://user@localhost/db"));
+        assert_eq!(options.time_zone.as_deref(), Some("+00:00"));
+        assert_eq!(options.charset, "utf8mb4");
+        assert!(options.pipes_as_concat);
+    }
+
+    #[test]
+    fn from_query_pairs_reads_timezone_and_charset() {
+        let options = MysqlConnectionOptions::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?timezone=America%2FNew_York&charset=latin1",
+        ));
+        assert_eq!(options.time_zone.as_deref(), Some("America/New_York"));
+        assert_eq!(options.charset, "latin1");
+    }
+
+    #[test]
+    fn from_query_pairs_timezone_system_disables_forced_time_zone() {
+        let options = MysqlConnectionOptions::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?timezone=system",
+        ));
+        assert_eq!(options.time_zone, None);
+    }
+
+    #[test]
+    fn from_query_pairs_sql_mode_keep_disables_pipes_as_concat() {
+        let options = MysqlConnectionOptions::from_query_pairs(&parse_url(
+            "mysql://user@localhost/db?sql_mode=keep",
+        ));
+        assert!(!options.pipes_as_concat);
+    }
+}